@@ -0,0 +1,60 @@
+use std::time::Instant;
+
+use crate::iterative;
+
+fn generate_flat_array(count: usize) -> String {
+    let mut s = String::from("[");
+    for i in 0..count {
+        if i > 0 {
+            s.push(',');
+        }
+        s.push_str(&i.to_string());
+    }
+    s.push(']');
+    s
+}
+
+fn mb_per_sec(bytes: usize, elapsed: std::time::Duration) -> f64 {
+    (bytes as f64 / (1024.0 * 1024.0)) / elapsed.as_secs_f64()
+}
+
+/// Parses a generated `count`-element JSON array with both the recursive
+/// and iterative parsers and prints their throughput, to give a baseline
+/// for future optimization work.
+pub(crate) fn run_throughput_benchmark(count: usize) {
+    let input = generate_flat_array(count);
+
+    let start = Instant::now();
+    let recursive = crate::parse_json(&input);
+    let recursive_elapsed = start.elapsed();
+
+    let start = Instant::now();
+    let iterative = iterative::parse_iterative(&input);
+    let iterative_elapsed = start.elapsed();
+
+    assert!(recursive.is_ok() && iterative.is_ok());
+
+    println!(
+        "recursive:  {} bytes in {:?} ({:.2} MB/s)",
+        input.len(),
+        recursive_elapsed,
+        mb_per_sec(input.len(), recursive_elapsed)
+    );
+    println!(
+        "iterative:  {} bytes in {:?} ({:.2} MB/s)",
+        input.len(),
+        iterative_elapsed,
+        mb_per_sec(input.len(), iterative_elapsed)
+    );
+}
+
+/// Parses a deeply nested array that would overflow the call stack under
+/// `crate::parse_json`'s recursive descent, demonstrating that the
+/// iterative parser handles it safely.
+pub(crate) fn run_deep_nesting_check(depth: usize) {
+    let input = format!("{}{}", "[".repeat(depth), "]".repeat(depth));
+    match iterative::parse_iterative(&input) {
+        Ok(_) => println!("iterative parser handled {} levels of nesting", depth),
+        Err(e) => println!("iterative parser failed at depth {}: {:?}", depth, e),
+    }
+}
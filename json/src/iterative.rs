@@ -0,0 +1,61 @@
+use crate::tokenizer::{self, ParseError, TokenKind};
+use crate::JsonValue;
+
+enum Frame {
+    Array(Vec<JsonValue>),
+    Object(Vec<(String, JsonValue)>, Option<String>),
+}
+
+fn emit(stack: &mut [Frame], result: &mut Option<JsonValue>, value: JsonValue) {
+    match stack.last_mut() {
+        Some(Frame::Array(items)) => items.push(value),
+        Some(Frame::Object(pairs, key)) => {
+            let k = key.take().expect("object value without a pending key");
+            pairs.push((k, value));
+        }
+        None => *result = Some(value),
+    }
+}
+
+/// Parses `input` the same as [`crate::parse_json`], but without native
+/// recursion: container frames live on a heap `Vec` instead of the call
+/// stack, so arbitrarily deep nesting can't overflow it.
+pub(crate) fn parse_iterative(input: &str) -> Result<JsonValue, ParseError> {
+    let tokens = tokenizer::tokenize(input)?;
+    tokenizer::validate(&tokens)?;
+
+    let mut stack: Vec<Frame> = Vec::new();
+    let mut result: Option<JsonValue> = None;
+
+    for tok in &tokens {
+        match &tok.kind {
+            TokenKind::BeginArray => stack.push(Frame::Array(Vec::new())),
+            TokenKind::BeginObject => stack.push(Frame::Object(Vec::new(), None)),
+            TokenKind::EndArray => {
+                if let Some(Frame::Array(items)) = stack.pop() {
+                    emit(&mut stack, &mut result, JsonValue::Array(items));
+                }
+            }
+            TokenKind::EndObject => {
+                if let Some(Frame::Object(pairs, _)) = stack.pop() {
+                    emit(&mut stack, &mut result, JsonValue::Object(pairs));
+                }
+            }
+            TokenKind::Str(s) => match stack.last_mut() {
+                Some(Frame::Object(_, key @ None)) => *key = Some(s.clone()),
+                _ => emit(&mut stack, &mut result, JsonValue::Str(s.clone())),
+            },
+            TokenKind::Num(n) => emit(&mut stack, &mut result, JsonValue::Num(*n)),
+            TokenKind::Bool(b) => emit(&mut stack, &mut result, JsonValue::Bool(*b)),
+            TokenKind::Null => emit(&mut stack, &mut result, JsonValue::Null),
+            TokenKind::Colon | TokenKind::Comma => {}
+        }
+    }
+
+    result.ok_or_else(|| ParseError {
+        line: 1,
+        col: 1,
+        expected: "a JSON value".to_string(),
+        found: "end of input".to_string(),
+    })
+}
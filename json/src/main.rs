@@ -1,19 +1,29 @@
 use nom::{
     branch::alt,
     bytes::complete::tag,
-    character::complete::{char, digit1, multispace0},
-    combinator::{map, recognize},
-    multi::{separated_list0, separated_list1},
-    sequence::{delimited, pair, preceded, separated_pair, terminated},
+    character::complete::{char, digit0, digit1, multispace0, one_of},
+    combinator::{map, map_res, recognize},
+    error::{Error as NomError, ErrorKind},
+    multi::separated_list0,
+    sequence::{delimited, pair, preceded, separated_pair, terminated, tuple},
     IResult,
 };
 
-use nom::bytes::complete::take_while;
+use nom::bytes::complete::take;
 use nom::combinator::opt;
 
+use std::collections::HashMap;
+
+mod bench;
+mod iterative;
+mod serializer;
+mod tokenizer;
+
+use tokenizer::ParseError;
+
 #[derive(Debug)]
 #[allow(dead_code)]
-enum JsonValue {
+pub(crate) enum JsonValue {
     Null,
     Num(f64),
     Bool(bool),
@@ -22,6 +32,57 @@ enum JsonValue {
     Object(Vec<(String, JsonValue)>),
 }
 
+#[allow(dead_code)]
+impl JsonValue {
+    /// Looks up `key` in an object, returning `None` for any other variant
+    /// or a missing key.
+    pub(crate) fn get(&self, key: &str) -> Option<&JsonValue> {
+        match self {
+            JsonValue::Object(pairs) => pairs.iter().find(|(k, _)| k == key).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    /// Indexes into an array, returning `None` for any other variant or an
+    /// out-of-bounds index.
+    pub(crate) fn index(&self, i: usize) -> Option<&JsonValue> {
+        match self {
+            JsonValue::Array(items) => items.get(i),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn as_f64(&self) -> Option<f64> {
+        match self {
+            JsonValue::Num(n) => Some(*n),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn as_bool(&self) -> Option<bool> {
+        match self {
+            JsonValue::Bool(b) => Some(*b),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn as_str(&self) -> Option<&str> {
+        match self {
+            JsonValue::Str(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    /// Converts an object into a `HashMap`, dropping the insertion order
+    /// that `Object`'s `Vec` preserves for the serializer.
+    pub(crate) fn into_map(self) -> Option<HashMap<String, JsonValue>> {
+        match self {
+            JsonValue::Object(pairs) => Some(pairs.into_iter().collect()),
+            _ => None,
+        }
+    }
+}
+
 fn parse_null(input: &str) -> IResult<&str, JsonValue> {
     map(tag("null"), |_| JsonValue::Null)(input)
 }
@@ -33,16 +94,83 @@ fn parse_bool(input: &str) -> IResult<&str, JsonValue> {
     ))(input)
 }
 
-fn parse_num(input: &str) -> IResult<&str, JsonValue> {
-    map(recognize(pair(opt(char('-')), digit1)), |s: &str| {
-        JsonValue::Num(s.parse().unwrap())
-    })(input)
+pub(crate) fn parse_num(input: &str) -> IResult<&str, JsonValue> {
+    map_res(
+        recognize(tuple((
+            opt(char('-')),
+            alt((recognize(char('0')), recognize(pair(one_of("123456789"), digit0)))),
+            opt(pair(char('.'), digit1)),
+            opt(tuple((one_of("eE"), opt(one_of("+-")), digit1))),
+        ))),
+        |s: &str| s.parse::<f64>().map(JsonValue::Num),
+    )(input)
+}
+
+fn parse_hex4(input: &str) -> IResult<&str, u16> {
+    map_res(take(4usize), |s: &str| u16::from_str_radix(s, 16))(input)
+}
+
+/// Decodes the body of a JSON string (the span between the surrounding
+/// quotes), resolving escape sequences and `\uXXXX`/surrogate-pair
+/// sequences along the way. Returns the unconsumed input starting at the
+/// closing quote.
+pub(crate) fn parse_str_body(mut input: &str) -> IResult<&str, String> {
+    let mut out = String::new();
+    loop {
+        match input.chars().next() {
+            None => return Err(nom::Err::Error(NomError::new(input, ErrorKind::Eof))),
+            Some('"') => return Ok((input, out)),
+            Some('\\') => {
+                input = &input[1..];
+                let escape = input
+                    .chars()
+                    .next()
+                    .ok_or_else(|| nom::Err::Error(NomError::new(input, ErrorKind::Escaped)))?;
+                input = &input[escape.len_utf8()..];
+                match escape {
+                    '"' => out.push('"'),
+                    '\\' => out.push('\\'),
+                    '/' => out.push('/'),
+                    'b' => out.push('\u{8}'),
+                    'f' => out.push('\u{c}'),
+                    'n' => out.push('\n'),
+                    'r' => out.push('\r'),
+                    't' => out.push('\t'),
+                    'u' => {
+                        let (rest, high) = parse_hex4(input)?;
+                        input = rest;
+                        let code_point = if (0xD800..=0xDBFF).contains(&high) {
+                            let (rest, _) = tag("\\u")(input)?;
+                            let (rest, low) = parse_hex4(rest)?;
+                            if !(0xDC00..=0xDFFF).contains(&low) {
+                                return Err(nom::Err::Error(NomError::new(input, ErrorKind::Verify)));
+                            }
+                            input = rest;
+                            (((high - 0xD800) as u32) << 10) + (low - 0xDC00) as u32 + 0x10000
+                        } else if (0xDC00..=0xDFFF).contains(&high) {
+                            return Err(nom::Err::Error(NomError::new(input, ErrorKind::Verify)));
+                        } else {
+                            high as u32
+                        };
+                        let c = char::from_u32(code_point)
+                            .ok_or_else(|| nom::Err::Error(NomError::new(input, ErrorKind::Verify)))?;
+                        out.push(c);
+                    }
+                    _ => return Err(nom::Err::Error(NomError::new(input, ErrorKind::Escaped))),
+                }
+            }
+            Some(c) => {
+                input = &input[c.len_utf8()..];
+                out.push(c);
+            }
+        }
+    }
 }
 
 fn parse_str(input: &str) -> IResult<&str, JsonValue> {
     map(
-        delimited(char('"'), take_while(|c| c != '"'), char('"')),
-        |s: &str| JsonValue::Str(s.to_string()),
+        delimited(char('"'), parse_str_body, char('"')),
+        JsonValue::Str,
     )(input)
 }
 
@@ -74,9 +202,18 @@ fn parse_array(input: &str) -> IResult<&str, JsonValue> {
     )(input)
 }
 
-fn parse_pair(input: &str) -> IResult<&str, (JsonValue, JsonValue)> {
+fn parse_key(input: &str) -> IResult<&str, String> {
+    map(parse_str, |v| match v {
+        JsonValue::Str(s) => s,
+        // parse_str only ever produces JsonValue::Str; kept as a guard
+        // rather than silently accepting other variants.
+        _ => unreachable!("parse_str always yields JsonValue::Str"),
+    })(input)
+}
+
+fn parse_pair(input: &str) -> IResult<&str, (String, JsonValue)> {
     separated_pair(
-        preceded(multispace0, parse_str),
+        preceded(multispace0, parse_key),
         preceded(multispace0, char(':')),
         preceded(multispace0, parse_value),
     )(input)
@@ -86,25 +223,13 @@ fn parse_object(input: &str) -> IResult<&str, JsonValue> {
     map(
         delimited(
             char('{'),
-            separated_list1(
+            separated_list0(
                 preceded(multispace0, char(',')),
                 preceded(multispace0, parse_pair),
             ),
             preceded(multispace0, char('}')),
         ),
-        |pairs| {
-            JsonValue::Object(
-                pairs
-                    .into_iter()
-                    .map(|(k, v)| {
-                        if let JsonValue::Str(key) = k {
-                            return (key, v);
-                        }
-                        panic!("key")
-                    })
-                    .collect(),
-            )
-        },
+        JsonValue::Object,
     )(input)
 }
 
@@ -112,6 +237,22 @@ fn parse_json(input: &str) -> IResult<&str, JsonValue> {
     terminated(parse_value, multispace0)(input)
 }
 
+/// Parses `input`, first running it through the tokenizer's structural
+/// validation so malformed JSON fails with a `ParseError` that carries a
+/// line/column instead of an opaque `nom` error.
+fn parse_json_with_diagnostics(input: &str) -> Result<JsonValue, ParseError> {
+    let tokens = tokenizer::tokenize(input)?;
+    tokenizer::validate(&tokens)?;
+    parse_json(input)
+        .map(|(_, value)| value)
+        .map_err(|_| ParseError {
+            line: 1,
+            col: 1,
+            expected: "valid JSON".to_string(),
+            found: "malformed input".to_string(),
+        })
+}
+
 fn main() {
     // println!("{:?}",parse_null( input:"null"));
     // println!("{:?}", parse_bool( input: "true"));
@@ -138,4 +279,28 @@ fn main() {
         Ok((_, json)) => println!("{:#?}", json),
         Err(e) => println!("Error:{:?}", e),
     }
+
+    // Malformed input now gets a line/column-aware error instead of
+    // an opaque nom error or a panic.
+    let broken = r#"{"a": 1, "b":}"#;
+    match parse_json_with_diagnostics(broken) {
+        Ok(json) => println!("{:#?}", json),
+        Err(e) => println!("ParseError:{:?}", e),
+    }
+
+    if let Ok((_, json)) = parse_json(json_str) {
+        println!("{}", serializer::to_string(&json));
+        println!("{}", serializer::to_string_pretty(&json, 2));
+
+        println!("age = {:?}", json.get("age").and_then(JsonValue::as_f64));
+        println!("scores[1] = {:?}", json.get("scores").and_then(|v| v.index(1)));
+    }
+
+    match parse_json("{}") {
+        Ok((_, json)) => println!("{:#?}", json),
+        Err(e) => println!("Error:{:?}", e),
+    }
+
+    bench::run_throughput_benchmark(100_000);
+    bench::run_deep_nesting_check(50_000);
 }
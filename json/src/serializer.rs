@@ -0,0 +1,122 @@
+use crate::JsonValue;
+
+fn escape_str(s: &str, out: &mut String) {
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            '\u{8}' => out.push_str("\\b"),
+            '\u{c}' => out.push_str("\\f"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c if c.is_ascii() => out.push(c),
+            c => {
+                let code_point = c as u32;
+                if code_point > 0xFFFF {
+                    let code_point = code_point - 0x10000;
+                    let high = 0xD800 + (code_point >> 10);
+                    let low = 0xDC00 + (code_point & 0x3FF);
+                    out.push_str(&format!("\\u{:04x}\\u{:04x}", high, low));
+                } else {
+                    out.push_str(&format!("\\u{:04x}", code_point));
+                }
+            }
+        }
+    }
+}
+
+fn format_num(n: f64) -> String {
+    if n.is_finite() && n.fract() == 0.0 && n.abs() < 1e15 {
+        format!("{}", n as i64)
+    } else {
+        format!("{}", n)
+    }
+}
+
+fn write_value(value: &JsonValue, out: &mut String) {
+    match value {
+        JsonValue::Null => out.push_str("null"),
+        JsonValue::Bool(b) => out.push_str(if *b { "true" } else { "false" }),
+        JsonValue::Num(n) => out.push_str(&format_num(*n)),
+        JsonValue::Str(s) => {
+            out.push('"');
+            escape_str(s, out);
+            out.push('"');
+        }
+        JsonValue::Array(items) => {
+            out.push('[');
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write_value(item, out);
+            }
+            out.push(']');
+        }
+        JsonValue::Object(pairs) => {
+            out.push('{');
+            for (i, (key, val)) in pairs.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                out.push('"');
+                escape_str(key, out);
+                out.push_str("\":");
+                write_value(val, out);
+            }
+            out.push('}');
+        }
+    }
+}
+
+/// Renders `value` as compact JSON.
+pub(crate) fn to_string(value: &JsonValue) -> String {
+    let mut out = String::new();
+    write_value(value, &mut out);
+    out
+}
+
+fn write_value_pretty(value: &JsonValue, out: &mut String, indent: usize, level: usize) {
+    match value {
+        JsonValue::Array(items) if !items.is_empty() => {
+            out.push_str("[\n");
+            for (i, item) in items.iter().enumerate() {
+                out.push_str(&" ".repeat(indent * (level + 1)));
+                write_value_pretty(item, out, indent, level + 1);
+                if i + 1 < items.len() {
+                    out.push(',');
+                }
+                out.push('\n');
+            }
+            out.push_str(&" ".repeat(indent * level));
+            out.push(']');
+        }
+        JsonValue::Object(pairs) if !pairs.is_empty() => {
+            out.push_str("{\n");
+            for (i, (key, val)) in pairs.iter().enumerate() {
+                out.push_str(&" ".repeat(indent * (level + 1)));
+                out.push('"');
+                escape_str(key, out);
+                out.push_str("\": ");
+                write_value_pretty(val, out, indent, level + 1);
+                if i + 1 < pairs.len() {
+                    out.push(',');
+                }
+                out.push('\n');
+            }
+            out.push_str(&" ".repeat(indent * level));
+            out.push('}');
+        }
+        // Scalars and empty containers render the same either way.
+        _ => write_value(value, out),
+    }
+}
+
+/// Renders `value` as JSON indented by `indent` spaces per nesting level.
+pub(crate) fn to_string_pretty(value: &JsonValue, indent: usize) -> String {
+    let mut out = String::new();
+    write_value_pretty(value, &mut out, indent, 0);
+    out
+}
@@ -0,0 +1,283 @@
+use nom::bytes::complete::tag;
+
+use crate::{parse_num, parse_str_body, JsonValue};
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum TokenKind {
+    BeginObject,
+    EndObject,
+    BeginArray,
+    EndArray,
+    Colon,
+    Comma,
+    Str(String),
+    Num(f64),
+    Bool(bool),
+    Null,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Token {
+    pub(crate) kind: TokenKind,
+    pub(crate) line: usize,
+    pub(crate) col: usize,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct ParseError {
+    pub(crate) line: usize,
+    pub(crate) col: usize,
+    pub(crate) expected: String,
+    pub(crate) found: String,
+}
+
+fn describe(kind: &TokenKind) -> String {
+    match kind {
+        TokenKind::BeginObject => "'{'".to_string(),
+        TokenKind::EndObject => "'}'".to_string(),
+        TokenKind::BeginArray => "'['".to_string(),
+        TokenKind::EndArray => "']'".to_string(),
+        TokenKind::Colon => "':'".to_string(),
+        TokenKind::Comma => "','".to_string(),
+        TokenKind::Str(s) => format!("string {:?}", s),
+        TokenKind::Num(n) => format!("number {}", n),
+        TokenKind::Bool(b) => format!("bool {}", b),
+        TokenKind::Null => "null".to_string(),
+    }
+}
+
+fn advance(line: &mut usize, col: &mut usize, consumed: &str) {
+    for c in consumed.chars() {
+        if c == '\n' {
+            *line += 1;
+            *col = 1;
+        } else {
+            *col += 1;
+        }
+    }
+}
+
+/// Scans `input` into a flat token stream, recording the line/column each
+/// token starts at so later stages can report precise error locations.
+pub(crate) fn tokenize(input: &str) -> Result<Vec<Token>, ParseError> {
+    let mut tokens = Vec::new();
+    let mut rest = input;
+    let mut line = 1usize;
+    let mut col = 1usize;
+
+    while let Some(c) = rest.chars().next() {
+        if c.is_whitespace() {
+            let ws_len = c.len_utf8();
+            advance(&mut line, &mut col, &rest[..ws_len]);
+            rest = &rest[ws_len..];
+            continue;
+        }
+
+        let (start_line, start_col) = (line, col);
+
+        match c {
+            '{' => {
+                tokens.push(Token { kind: TokenKind::BeginObject, line, col });
+                advance(&mut line, &mut col, "{");
+                rest = &rest[1..];
+            }
+            '}' => {
+                tokens.push(Token { kind: TokenKind::EndObject, line, col });
+                advance(&mut line, &mut col, "}");
+                rest = &rest[1..];
+            }
+            '[' => {
+                tokens.push(Token { kind: TokenKind::BeginArray, line, col });
+                advance(&mut line, &mut col, "[");
+                rest = &rest[1..];
+            }
+            ']' => {
+                tokens.push(Token { kind: TokenKind::EndArray, line, col });
+                advance(&mut line, &mut col, "]");
+                rest = &rest[1..];
+            }
+            ':' => {
+                tokens.push(Token { kind: TokenKind::Colon, line, col });
+                advance(&mut line, &mut col, ":");
+                rest = &rest[1..];
+            }
+            ',' => {
+                tokens.push(Token { kind: TokenKind::Comma, line, col });
+                advance(&mut line, &mut col, ",");
+                rest = &rest[1..];
+            }
+            '"' => {
+                let after_quote = &rest[1..];
+                let (remaining, s) = parse_str_body(after_quote).map_err(|e| {
+                    let at_end = matches!(&e, nom::Err::Error(err) if err.input.is_empty());
+                    ParseError {
+                        line: start_line,
+                        col: start_col,
+                        expected: if at_end { "closing '\"'" } else { "a valid escape sequence" }.to_string(),
+                        found: if at_end { "end of input" } else { "an invalid escape sequence" }.to_string(),
+                    }
+                })?;
+                let consumed = &after_quote[..after_quote.len() - remaining.len()];
+                tokens.push(Token { kind: TokenKind::Str(s), line: start_line, col: start_col });
+                advance(&mut line, &mut col, "\"");
+                advance(&mut line, &mut col, consumed);
+                advance(&mut line, &mut col, "\"");
+                rest = &remaining[1..];
+            }
+            '-' | '0'..='9' => {
+                let (remaining, value) = parse_num(rest).map_err(|_| ParseError {
+                    line: start_line,
+                    col: start_col,
+                    expected: "a number".to_string(),
+                    found: "malformed number".to_string(),
+                })?;
+                let consumed = &rest[..rest.len() - remaining.len()];
+                let n = match value {
+                    JsonValue::Num(n) => n,
+                    _ => unreachable!("parse_num always yields JsonValue::Num"),
+                };
+                tokens.push(Token { kind: TokenKind::Num(n), line: start_line, col: start_col });
+                advance(&mut line, &mut col, consumed);
+                rest = remaining;
+            }
+            't' | 'f' | 'n' => {
+                let (remaining, kind) = if let Ok((r, _)) = tag::<_, _, ()>("true")(rest) {
+                    (r, TokenKind::Bool(true))
+                } else if let Ok((r, _)) = tag::<_, _, ()>("false")(rest) {
+                    (r, TokenKind::Bool(false))
+                } else if let Ok((r, _)) = tag::<_, _, ()>("null")(rest) {
+                    (r, TokenKind::Null)
+                } else {
+                    return Err(ParseError {
+                        line: start_line,
+                        col: start_col,
+                        expected: "'true', 'false' or 'null'".to_string(),
+                        found: rest.chars().take(5).collect(),
+                    });
+                };
+                let consumed = &rest[..rest.len() - remaining.len()];
+                tokens.push(Token { kind, line: start_line, col: start_col });
+                advance(&mut line, &mut col, consumed);
+                rest = remaining;
+            }
+            other => {
+                return Err(ParseError {
+                    line: start_line,
+                    col: start_col,
+                    expected: "a JSON value or structural token".to_string(),
+                    found: other.to_string(),
+                });
+            }
+        }
+    }
+
+    Ok(tokens)
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Container {
+    Object,
+    Array,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Expect {
+    Value,
+    ObjectKeyOrClose,
+    ObjectKey,
+    Colon,
+    ArrayValueOrClose,
+    ArrayValue,
+    CommaOrObjectClose,
+    CommaOrArrayClose,
+    Done,
+}
+
+fn expect_description(expect: Expect) -> &'static str {
+    match expect {
+        Expect::Value | Expect::ArrayValue => "a value",
+        Expect::ObjectKeyOrClose => "a string key or '}'",
+        Expect::ObjectKey => "a string key",
+        Expect::Colon => "':'",
+        Expect::ArrayValueOrClose => "a value or ']'",
+        Expect::CommaOrObjectClose => "',' or '}'",
+        Expect::CommaOrArrayClose => "',' or ']'",
+        Expect::Done => "end of input",
+    }
+}
+
+fn after_value(stack: &[Container]) -> Expect {
+    match stack.last() {
+        Some(Container::Object) => Expect::CommaOrObjectClose,
+        Some(Container::Array) => Expect::CommaOrArrayClose,
+        None => Expect::Done,
+    }
+}
+
+/// Walks `tokens` with an explicit container stack, checking that each
+/// token is legal given what the enclosing object/array and the pending
+/// key expect next.
+pub(crate) fn validate(tokens: &[Token]) -> Result<(), ParseError> {
+    let mut stack: Vec<Container> = Vec::new();
+    let mut expect = Expect::Value;
+
+    let illegal = |tok: &Token, expect: Expect| ParseError {
+        line: tok.line,
+        col: tok.col,
+        expected: expect_description(expect).to_string(),
+        found: describe(&tok.kind),
+    };
+
+    for tok in tokens {
+        if expect == Expect::Done {
+            return Err(illegal(tok, expect));
+        }
+        expect = match (expect, &tok.kind) {
+            (Expect::Value | Expect::ArrayValueOrClose | Expect::ArrayValue, TokenKind::BeginObject) => {
+                stack.push(Container::Object);
+                Expect::ObjectKeyOrClose
+            }
+            (Expect::Value | Expect::ArrayValueOrClose | Expect::ArrayValue, TokenKind::BeginArray) => {
+                stack.push(Container::Array);
+                Expect::ArrayValueOrClose
+            }
+            (
+                Expect::Value | Expect::ArrayValueOrClose | Expect::ArrayValue,
+                TokenKind::Str(_) | TokenKind::Num(_) | TokenKind::Bool(_) | TokenKind::Null,
+            ) => after_value(&stack),
+            (Expect::ObjectKeyOrClose, TokenKind::EndObject) => {
+                stack.pop();
+                after_value(&stack)
+            }
+            (Expect::ObjectKeyOrClose | Expect::ObjectKey, TokenKind::Str(_)) => Expect::Colon,
+            (Expect::Colon, TokenKind::Colon) => Expect::Value,
+            (Expect::ArrayValueOrClose, TokenKind::EndArray) => {
+                stack.pop();
+                after_value(&stack)
+            }
+            (Expect::CommaOrObjectClose, TokenKind::Comma) => Expect::ObjectKey,
+            (Expect::CommaOrObjectClose, TokenKind::EndObject) => {
+                stack.pop();
+                after_value(&stack)
+            }
+            (Expect::CommaOrArrayClose, TokenKind::Comma) => Expect::ArrayValue,
+            (Expect::CommaOrArrayClose, TokenKind::EndArray) => {
+                stack.pop();
+                after_value(&stack)
+            }
+            (expect, _) => return Err(illegal(tok, expect)),
+        };
+    }
+
+    if expect != Expect::Done {
+        let (line, col) = tokens.last().map(|t| (t.line, t.col)).unwrap_or((1, 1));
+        return Err(ParseError {
+            line,
+            col,
+            expected: expect_description(expect).to_string(),
+            found: "end of input".to_string(),
+        });
+    }
+
+    Ok(())
+}